@@ -0,0 +1,74 @@
+use async_trait::async_trait;
+
+use crate::traits::message_sender::IbcMessageSender;
+use crate::traits::messages::rebuild::MessageRebuilder;
+use crate::traits::relay_types::{RelayContext, RelayTypes};
+use crate::traits::retry::{RetryConfig, RetryableError, RetryableErrorKind};
+
+/// An [`IbcMessageSender`] decorator that retries a failed submission with
+/// bounded exponential backoff.
+///
+/// Transient failures (mempool rejection, sequence mismatch) are retried
+/// with the same message bytes. A stale-proof failure instead asks the
+/// context to rebuild the message against a fresh height before resending
+/// it, since resending the same proof would just fail again. Once
+/// `max_retries` is exhausted, the accumulated attempt history is surfaced
+/// via [`RetryableError::retries_exhausted`].
+pub struct RetryingMessageSender<Inner> {
+    inner: Inner,
+    config: RetryConfig,
+}
+
+impl<Inner> RetryingMessageSender<Inner> {
+    pub fn new(inner: Inner, config: RetryConfig) -> Self {
+        Self { inner, config }
+    }
+}
+
+#[async_trait]
+impl<Context, Target, Relay, Error, Inner> IbcMessageSender<Context, Target>
+    for RetryingMessageSender<Inner>
+where
+    Relay: RelayTypes<Error = Error>,
+    Context: RelayContext<RelayTypes = Relay, Error = Error>,
+    Context: MessageRebuilder<Relay>,
+    Error: RetryableError,
+    Inner: IbcMessageSender<Context, Target, Message = Relay::Message>,
+{
+    type Message = Relay::Message;
+
+    async fn send_message(
+        &self,
+        context: &Context,
+        message: Self::Message,
+    ) -> Result<(), Error> {
+        let mut message = message;
+        let mut attempts = Vec::new();
+        let mut delay = self.config.base_delay;
+
+        loop {
+            match self.inner.send_message(context, message.clone()).await {
+                Ok(()) => return Ok(()),
+                Err(error) => {
+                    let kind = match error.retryable_kind() {
+                        Some(kind) => kind,
+                        None => return Err(error),
+                    };
+
+                    attempts.push(error);
+
+                    if attempts.len() as u32 > self.config.max_retries {
+                        return Err(Error::retries_exhausted(attempts));
+                    }
+
+                    if kind == RetryableErrorKind::StaleProof {
+                        message = context.rebuild_message(&message).await?;
+                    }
+
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use async_trait::async_trait;
+
+use crate::traits::message_sender::{IbcMessageSender, IbcMessageSenderExt, MessageSenderContext};
+use crate::traits::messages::ack_packet::AckPacketMessageBuilder;
+use crate::traits::packet_relayer::PacketRelayer;
+use crate::traits::queries::status::{ChainStatus, ChainStatusQuerier};
+use crate::traits::relay_types::{RelayContext, RelayTypes};
+use crate::traits::target::SourceTarget;
+use crate::types::aliases::Packet;
+
+pub struct AckPacketRelayer;
+
+#[async_trait]
+impl<Context, Relay, Error, Sender> PacketRelayer<Context> for AckPacketRelayer
+where
+    Relay: RelayTypes<Error = Error>,
+    Context: RelayContext<RelayTypes = Relay, Error = Error>,
+    Context: AckPacketMessageBuilder<Relay>,
+    Context::DstChainContext: ChainStatusQuerier<Relay::DstChain>,
+    Context: MessageSenderContext<SourceTarget, Sender = Sender>,
+    Sender: IbcMessageSender<Context, SourceTarget>,
+{
+    type Return = ();
+
+    async fn relay_packet(&self, context: &Context, packet: Packet<Relay>) -> Result<(), Error> {
+        let destination_height = context
+            .destination_context()
+            .query_chain_status()
+            .await?
+            .height();
+
+        let message = context
+            .build_ack_packet_message(&destination_height, &packet)
+            .await?;
+
+        context
+            .message_sender()
+            .send_message(context, message)
+            .await?;
+
+        Ok(())
+    }
+}
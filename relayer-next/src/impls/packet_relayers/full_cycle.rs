@@ -0,0 +1,62 @@
+use async_trait::async_trait;
+
+use crate::impls::packet_relayers::ack_packet::AckPacketRelayer;
+use crate::impls::packet_relayers::receive_packet::ReceivePacketRelayer;
+use crate::impls::packet_relayers::timeout_packet::{is_expired, TimeoutPacketRelayer};
+use crate::traits::message_sender::{IbcMessageSender, MessageSenderContext};
+use crate::traits::messages::ack_packet::AckPacketMessageBuilder;
+use crate::traits::messages::receive_packet::ReceivePacketMessageBuilder;
+use crate::traits::messages::timeout_packet::TimeoutPacketMessageBuilder;
+use crate::traits::packet_relayer::PacketRelayer;
+use crate::traits::queries::channel_closed::ChannelClosedQuerier;
+use crate::traits::queries::received_ack::ReceivedAckQuerier;
+use crate::traits::queries::received_packet::ReceivedPacketQuerier;
+use crate::traits::queries::status::ChainStatusQuerier;
+use crate::traits::relay_types::{RelayContext, RelayTypes};
+use crate::traits::target::{DestinationTarget, SourceTarget};
+use crate::types::aliases::Packet;
+
+/// A single entry point that drives a packet all the way to completion,
+/// in either direction. If the destination has already received the packet,
+/// it does nothing when the source has already observed the ack and
+/// dispatches to [`AckPacketRelayer`] otherwise. If not, it dispatches to
+/// [`TimeoutPacketRelayer`] when the packet has expired against the
+/// destination chain's status, and to [`ReceivePacketRelayer`] otherwise.
+pub struct FullCycleRelayer;
+
+#[async_trait]
+impl<Context, Relay, Error, SrcSender, DstSender> PacketRelayer<Context> for FullCycleRelayer
+where
+    Relay: RelayTypes<Error = Error>,
+    Context: RelayContext<RelayTypes = Relay, Error = Error>,
+    Context: ReceivedPacketQuerier<Relay> + ReceivedAckQuerier<Relay> + ChannelClosedQuerier<Relay>,
+    Context: ReceivePacketMessageBuilder<Relay>,
+    Context: AckPacketMessageBuilder<Relay>,
+    Context: TimeoutPacketMessageBuilder<Relay>,
+    Context::SrcChainContext: ChainStatusQuerier<Relay::SrcChain>,
+    Context::DstChainContext: ChainStatusQuerier<Relay::DstChain>,
+    Context: MessageSenderContext<DestinationTarget, Sender = DstSender>,
+    Context: MessageSenderContext<SourceTarget, Sender = SrcSender>,
+    DstSender: IbcMessageSender<Context, DestinationTarget>,
+    SrcSender: IbcMessageSender<Context, SourceTarget>,
+{
+    type Return = ();
+
+    async fn relay_packet(&self, context: &Context, packet: Packet<Relay>) -> Result<(), Error> {
+        if context.is_packet_received(&packet).await? {
+            if context.is_ack_received(&packet).await? {
+                return Ok(());
+            }
+
+            return AckPacketRelayer.relay_packet(context, packet).await;
+        }
+
+        let destination_status = context.destination_context().query_chain_status().await?;
+
+        if is_expired(&packet, &destination_status) {
+            return TimeoutPacketRelayer.relay_packet(context, packet).await;
+        }
+
+        ReceivePacketRelayer.relay_packet(context, packet).await
+    }
+}
@@ -0,0 +1,57 @@
+use async_trait::async_trait;
+use ibc::timestamp::Expiry;
+
+use crate::traits::message_sender::{IbcMessageSender, IbcMessageSenderExt, MessageSenderContext};
+use crate::traits::messages::timeout_packet::TimeoutPacketMessageBuilder;
+use crate::traits::packet_relayer::PacketRelayer;
+use crate::traits::queries::channel_closed::ChannelClosedQuerier;
+use crate::traits::queries::status::{ChainStatus, ChainStatusQuerier};
+use crate::traits::relay_types::{RelayContext, RelayTypes};
+use crate::traits::target::SourceTarget;
+use crate::types::aliases::Packet;
+
+pub struct TimeoutPacketRelayer;
+
+pub(crate) fn is_expired<Relay: RelayTypes>(packet: &Packet<Relay>, status: &impl ChainStatus) -> bool {
+    packet.timeout_height.has_expired(&status.height())
+        || packet.timeout_timestamp.check_expiry(&status.timestamp()) == Expiry::Expired
+}
+
+#[async_trait]
+impl<Context, Relay, Error, Sender> PacketRelayer<Context> for TimeoutPacketRelayer
+where
+    Relay: RelayTypes<Error = Error>,
+    Context: RelayContext<RelayTypes = Relay, Error = Error>,
+    Context: TimeoutPacketMessageBuilder<Relay>,
+    Context: ChannelClosedQuerier<Relay>,
+    Context::DstChainContext: ChainStatusQuerier<Relay::DstChain>,
+    Context: MessageSenderContext<SourceTarget, Sender = Sender>,
+    Sender: IbcMessageSender<Context, SourceTarget>,
+{
+    type Return = ();
+
+    async fn relay_packet(&self, context: &Context, packet: Packet<Relay>) -> Result<(), Error> {
+        let destination_status = context.destination_context().query_chain_status().await?;
+
+        if !is_expired(&packet, &destination_status) {
+            return Ok(());
+        }
+
+        let message = if context.is_destination_channel_closed(&packet).await? {
+            context
+                .build_timeout_on_close_packet_message(&destination_status.height(), &packet)
+                .await?
+        } else {
+            context
+                .build_timeout_packet_message(&destination_status.height(), &packet)
+                .await?
+        };
+
+        context
+            .message_sender()
+            .send_message(context, message)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,17 @@
+use async_trait::async_trait;
+use ibc::Height;
+
+use crate::traits::relay_types::RelayTypes;
+use crate::types::aliases::Packet;
+
+/// Builds the message that acknowledges, on the source chain, a packet that
+/// the destination chain has already received and written an acknowledgement
+/// for.
+#[async_trait]
+pub trait AckPacketMessageBuilder<Relay: RelayTypes> {
+    async fn build_ack_packet_message(
+        &self,
+        destination_height: &Height,
+        packet: &Packet<Relay>,
+    ) -> Result<Relay::Message, Relay::Error>;
+}
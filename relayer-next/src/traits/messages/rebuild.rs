@@ -0,0 +1,20 @@
+use async_trait::async_trait;
+
+use crate::traits::relay_types::RelayTypes;
+
+/// Rebuilds a previously-built IBC message against a fresh proof height.
+///
+/// Implemented by a relay context in terms of whichever message builder
+/// produced the message in the first place (`ReceivePacketMessageBuilder`,
+/// `AckPacketMessageBuilder`, ...) and whichever chain that builder proves
+/// against, so that a generic decorator like
+/// [`crate::impls::message_senders::retry::RetryingMessageSender`] can
+/// recover from a stale-proof submission failure without knowing which
+/// packet action, or which side's height, it's rebuilding for.
+#[async_trait]
+pub trait MessageRebuilder<Relay: RelayTypes> {
+    async fn rebuild_message(
+        &self,
+        message: &Relay::Message,
+    ) -> Result<Relay::Message, Relay::Error>;
+}
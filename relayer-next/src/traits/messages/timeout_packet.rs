@@ -0,0 +1,25 @@
+use async_trait::async_trait;
+use ibc::Height;
+
+use crate::traits::relay_types::RelayTypes;
+use crate::types::aliases::Packet;
+
+/// Builds the message that closes out, on the source chain, a packet whose
+/// timeout has elapsed on the destination chain without being received.
+#[async_trait]
+pub trait TimeoutPacketMessageBuilder<Relay: RelayTypes> {
+    async fn build_timeout_packet_message(
+        &self,
+        destination_height: &Height,
+        packet: &Packet<Relay>,
+    ) -> Result<Relay::Message, Relay::Error>;
+
+    /// Same as `build_timeout_packet_message`, but for a packet whose
+    /// destination channel has already been closed, which requires a
+    /// `MsgTimeoutOnClose` rather than a plain `MsgTimeout`.
+    async fn build_timeout_on_close_packet_message(
+        &self,
+        destination_height: &Height,
+        packet: &Packet<Relay>,
+    ) -> Result<Relay::Message, Relay::Error>;
+}
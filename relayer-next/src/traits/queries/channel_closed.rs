@@ -0,0 +1,15 @@
+use async_trait::async_trait;
+
+use crate::traits::relay_types::RelayTypes;
+use crate::types::aliases::Packet;
+
+/// Tells whether the destination channel a packet targets has already been
+/// closed, which decides between a plain `MsgTimeout` and a
+/// `MsgTimeoutOnClose` in [`crate::impls::packet_relayers::timeout_packet`].
+#[async_trait]
+pub trait ChannelClosedQuerier<Relay: RelayTypes> {
+    async fn is_destination_channel_closed(
+        &self,
+        packet: &Packet<Relay>,
+    ) -> Result<bool, Relay::Error>;
+}
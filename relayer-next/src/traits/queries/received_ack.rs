@@ -0,0 +1,12 @@
+use async_trait::async_trait;
+
+use crate::traits::relay_types::RelayTypes;
+use crate::types::aliases::Packet;
+
+/// Tells whether the source chain has already observed the acknowledgement
+/// for a packet it sent, backed by `query_unreceived_acknowledgements` on the
+/// underlying `ChainEndpoint`.
+#[async_trait]
+pub trait ReceivedAckQuerier<Relay: RelayTypes> {
+    async fn is_ack_received(&self, packet: &Packet<Relay>) -> Result<bool, Relay::Error>;
+}
@@ -0,0 +1,11 @@
+use async_trait::async_trait;
+
+use crate::traits::relay_types::RelayTypes;
+use crate::types::aliases::Packet;
+
+/// Tells whether the destination chain has already received a packet,
+/// backed by `query_unreceived_packets` on the underlying `ChainEndpoint`.
+#[async_trait]
+pub trait ReceivedPacketQuerier<Relay: RelayTypes> {
+    async fn is_packet_received(&self, packet: &Packet<Relay>) -> Result<bool, Relay::Error>;
+}
@@ -0,0 +1,43 @@
+use core::time::Duration;
+
+/// How a message-submission error should be handled by
+/// [`crate::impls::message_senders::retry::RetryingMessageSender`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryableErrorKind {
+    /// Mempool rejection, sequence mismatch, or anything else where
+    /// resending the exact same message bytes is expected to work.
+    Transient,
+    /// The proof (or consensus state) the message was built against got
+    /// pruned or superseded; the message must be rebuilt at a fresh height
+    /// before resending.
+    StaleProof,
+}
+
+/// Lets a generic retry decorator classify and accumulate errors coming out
+/// of `IbcMessageSender::send_message` without knowing the concrete error
+/// type of the chains being relayed between.
+pub trait RetryableError: Sized {
+    /// Returns `None` for a fatal error that must not be retried.
+    fn retryable_kind(&self) -> Option<RetryableErrorKind>;
+
+    /// Builds the final error to surface once retries are exhausted,
+    /// carrying the full history of attempts.
+    fn retries_exhausted(attempts: Vec<Self>) -> Self;
+}
+
+/// Bounded exponential backoff parameters for
+/// [`crate::impls::message_senders::retry::RetryingMessageSender`].
+#[derive(Debug, Clone, Copy)]
+pub struct RetryConfig {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            base_delay: Duration::from_millis(500),
+        }
+    }
+}
@@ -0,0 +1,618 @@
+use std::sync::{Arc, Mutex};
+
+use lru::LruCache;
+use semver::Version;
+
+use tendermint_rpc::endpoint::broadcast::tx_sync;
+
+use ibc::{
+    core::{
+        ics02_client::{
+            client_consensus::{AnyConsensusState, AnyConsensusStateWithHeight},
+            client_state::{AnyClientState, IdentifiedAnyClientState},
+            events::UpdateClient,
+            misbehaviour::MisbehaviourEvidence,
+        },
+        ics03_connection::connection::{ConnectionEnd, IdentifiedConnectionEnd, State as ConnectionState},
+        ics04_channel::{
+            channel::{ChannelEnd, IdentifiedChannelEnd, State as ChannelState},
+            packet::{PacketMsgType, Sequence},
+        },
+        ics23_commitment::{commitment::CommitmentPrefix, merkle::MerkleProof},
+        ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId},
+    },
+    events::IbcEvent,
+    query::{QueryBlockRequest, QueryTxRequest},
+    Height,
+};
+
+use crate::{
+    account::Balance,
+    chain::{
+        client::ClientSettings,
+        endpoint::{ChainEndpoint, ChainStatus, HealthCheck},
+        requests::*,
+        tracking::TrackedMsgs,
+    },
+    config::ChainConfig,
+    error::Error,
+    event::monitor::{EventReceiver, TxMonitorCmd},
+    keyring::{KeyEntry, KeyRing},
+    light_client::{LightClient, Verified},
+};
+
+/// Results cached by [`CachingChain`] are assumed to be hot for the duration
+/// of a connection/channel handshake or a packet relay, not across restarts,
+/// so a modest bound keeps memory use predictable without tuning.
+const CACHE_CAPACITY: usize = 1000;
+
+/// Returns `false` for the raw latest-height sentinel (revision height `0`),
+/// used by the `proven_*` helpers below, since a query at that height
+/// observes whatever the chain head happens to be right now and must never
+/// be served from, or written into, the cache.
+fn is_cacheable(height: Height) -> bool {
+    !height.is_zero()
+}
+
+/// Same as [`is_cacheable`], but for the `QueryHeight` that `Query*Request`s
+/// carry instead of a raw `Height`: returns the specific height to cache
+/// under, or `None` for `QueryHeight::Latest`.
+fn cacheable_height(height: QueryHeight) -> Option<Height> {
+    match height {
+        QueryHeight::Latest => None,
+        QueryHeight::Specific(height) => Some(height),
+    }
+}
+
+fn new_cache<K: std::hash::Hash + Eq, V>() -> Mutex<LruCache<K, V>> {
+    Mutex::new(LruCache::new(CACHE_CAPACITY))
+}
+
+/// A [`ChainEndpoint`] wrapper that memoizes query results which are
+/// immutable once finalized: client/consensus states at a fixed height,
+/// connections once they reach their terminal `Open` state (ICS-3
+/// connections only ever move forward into `Open`), channels once they
+/// reach *their* terminal `Closed` state (ICS-4 channels can still close
+/// from `Open`, so `Open` itself isn't safe to cache), and the
+/// accompanying Merkle proofs. Bounded LRUs keyed by the request (plus
+/// height) avoid re-fetching the same consensus state or proof over and
+/// over during a handshake or packet relay.
+///
+/// Composes with [`super::cosmos::psql::PsqlChain`] by wrapping it, the same
+/// way any other `ChainEndpoint` can be wrapped.
+pub struct CachingChain<C: ChainEndpoint> {
+    inner: C,
+
+    client_state_cache: Mutex<LruCache<(ClientId, Height), AnyClientState>>,
+    consensus_state_cache: Mutex<LruCache<(ClientId, Height), AnyConsensusState>>,
+    connection_cache: Mutex<LruCache<ConnectionId, ConnectionEnd>>,
+    channel_cache: Mutex<LruCache<(PortId, ChannelId), ChannelEnd>>,
+
+    proven_client_state_cache: Mutex<LruCache<(ClientId, Height), (AnyClientState, MerkleProof)>>,
+    proven_connection_cache: Mutex<LruCache<(ConnectionId, Height), (ConnectionEnd, MerkleProof)>>,
+    proven_consensus_cache:
+        Mutex<LruCache<(ClientId, Height, Height), (AnyConsensusState, MerkleProof)>>,
+    proven_channel_cache: Mutex<LruCache<(PortId, ChannelId, Height), (ChannelEnd, MerkleProof)>>,
+    proven_packet_cache:
+        Mutex<LruCache<(PacketMsgType, PortId, ChannelId, Sequence, Height), (Vec<u8>, MerkleProof)>>,
+}
+
+impl<C: ChainEndpoint> CachingChain<C> {
+    pub fn new(inner: C) -> Self {
+        Self {
+            inner,
+            client_state_cache: new_cache(),
+            consensus_state_cache: new_cache(),
+            connection_cache: new_cache(),
+            channel_cache: new_cache(),
+            proven_client_state_cache: new_cache(),
+            proven_connection_cache: new_cache(),
+            proven_consensus_cache: new_cache(),
+            proven_channel_cache: new_cache(),
+            proven_packet_cache: new_cache(),
+        }
+    }
+}
+
+impl<C: ChainEndpoint> ChainEndpoint for CachingChain<C> {
+    type LightBlock = C::LightBlock;
+
+    type Header = C::Header;
+
+    type ConsensusState = C::ConsensusState;
+
+    type ClientState = C::ClientState;
+
+    type LightClient = CachingLightClient<C>;
+
+    fn bootstrap(config: ChainConfig, rt: Arc<tokio::runtime::Runtime>) -> Result<Self, Error> {
+        Ok(Self::new(C::bootstrap(config, rt)?))
+    }
+
+    fn init_light_client(&self) -> Result<Self::LightClient, Error> {
+        self.inner.init_light_client().map(CachingLightClient)
+    }
+
+    fn init_event_monitor(
+        &self,
+        rt: Arc<tokio::runtime::Runtime>,
+    ) -> Result<(EventReceiver, TxMonitorCmd), Error> {
+        self.inner.init_event_monitor(rt)
+    }
+
+    fn id(&self) -> &ChainId {
+        self.inner.id()
+    }
+
+    fn shutdown(self) -> Result<(), Error> {
+        self.inner.shutdown()
+    }
+
+    fn health_check(&self) -> Result<HealthCheck, Error> {
+        self.inner.health_check()
+    }
+
+    fn keybase(&self) -> &KeyRing {
+        self.inner.keybase()
+    }
+
+    fn keybase_mut(&mut self) -> &mut KeyRing {
+        self.inner.keybase_mut()
+    }
+
+    fn send_messages_and_wait_commit(
+        &mut self,
+        tracked_msgs: TrackedMsgs,
+    ) -> Result<Vec<IbcEvent>, Error> {
+        self.inner.send_messages_and_wait_commit(tracked_msgs)
+    }
+
+    fn send_messages_and_wait_check_tx(
+        &mut self,
+        tracked_msgs: TrackedMsgs,
+    ) -> Result<Vec<tx_sync::Response>, Error> {
+        self.inner.send_messages_and_wait_check_tx(tracked_msgs)
+    }
+
+    fn get_signer(&mut self) -> Result<ibc::signer::Signer, Error> {
+        self.inner.get_signer()
+    }
+
+    fn config(&self) -> ChainConfig {
+        ChainEndpoint::config(&self.inner)
+    }
+
+    fn get_key(&mut self) -> Result<KeyEntry, Error> {
+        self.inner.get_key()
+    }
+
+    fn add_key(&mut self, key_name: &str, key: KeyEntry) -> Result<(), Error> {
+        self.inner.add_key(key_name, key)
+    }
+
+    fn ibc_version(&self) -> Result<Option<Version>, Error> {
+        self.inner.ibc_version()
+    }
+
+    fn query_balance(&self) -> Result<Balance, Error> {
+        self.inner.query_balance()
+    }
+
+    fn query_commitment_prefix(&self) -> Result<CommitmentPrefix, Error> {
+        self.inner.query_commitment_prefix()
+    }
+
+    fn query_application_status(&self) -> Result<ChainStatus, Error> {
+        self.inner.query_application_status()
+    }
+
+    fn query_clients(
+        &self,
+        request: QueryClientStatesRequest,
+    ) -> Result<Vec<IdentifiedAnyClientState>, Error> {
+        self.inner.query_clients(request)
+    }
+
+    fn query_client_state(
+        &self,
+        request: QueryClientStateRequest,
+    ) -> Result<AnyClientState, Error> {
+        let height = match cacheable_height(request.height) {
+            Some(height) => height,
+            None => return self.inner.query_client_state(request),
+        };
+
+        let key = (request.client_id.clone(), height);
+
+        if let Some(cached) = self.client_state_cache.lock().unwrap().get(&key) {
+            return Ok(cached.clone());
+        }
+
+        let client_state = self.inner.query_client_state(request)?;
+        self.client_state_cache
+            .lock()
+            .unwrap()
+            .put(key, client_state.clone());
+
+        Ok(client_state)
+    }
+
+    fn query_consensus_states(
+        &self,
+        request: QueryConsensusStatesRequest,
+    ) -> Result<Vec<AnyConsensusStateWithHeight>, Error> {
+        self.inner.query_consensus_states(request)
+    }
+
+    fn query_consensus_state(
+        &self,
+        request: QueryConsensusStateRequest,
+    ) -> Result<AnyConsensusState, Error> {
+        if cacheable_height(request.query_height).is_none() {
+            return self.inner.query_consensus_state(request);
+        }
+
+        let key = (request.client_id.clone(), request.consensus_height);
+
+        if let Some(cached) = self.consensus_state_cache.lock().unwrap().get(&key) {
+            return Ok(cached.clone());
+        }
+
+        let consensus_state = self.inner.query_consensus_state(request)?;
+        self.consensus_state_cache
+            .lock()
+            .unwrap()
+            .put(key, consensus_state.clone());
+
+        Ok(consensus_state)
+    }
+
+    fn query_upgraded_client_state(
+        &self,
+        request: QueryUpgradedClientStateRequest,
+    ) -> Result<(AnyClientState, MerkleProof), Error> {
+        self.inner.query_upgraded_client_state(request)
+    }
+
+    fn query_upgraded_consensus_state(
+        &self,
+        request: QueryUpgradedConsensusStateRequest,
+    ) -> Result<(AnyConsensusState, MerkleProof), Error> {
+        self.inner.query_upgraded_consensus_state(request)
+    }
+
+    fn query_connections(
+        &self,
+        request: QueryConnectionsRequest,
+    ) -> Result<Vec<IdentifiedConnectionEnd>, Error> {
+        self.inner.query_connections(request)
+    }
+
+    fn query_client_connections(
+        &self,
+        request: QueryClientConnectionsRequest,
+    ) -> Result<Vec<ConnectionId>, Error> {
+        self.inner.query_client_connections(request)
+    }
+
+    fn query_connection(&self, request: QueryConnectionRequest) -> Result<ConnectionEnd, Error> {
+        if cacheable_height(request.height).is_none() {
+            return self.inner.query_connection(request);
+        }
+
+        let connection_id = request.connection_id.clone();
+
+        if let Some(cached) = self.connection_cache.lock().unwrap().get(&connection_id) {
+            return Ok(cached.clone());
+        }
+
+        let connection = self.inner.query_connection(request)?;
+
+        if *connection.state() == ConnectionState::Open {
+            self.connection_cache
+                .lock()
+                .unwrap()
+                .put(connection_id, connection.clone());
+        }
+
+        Ok(connection)
+    }
+
+    fn query_connection_channels(
+        &self,
+        request: QueryConnectionChannelsRequest,
+    ) -> Result<Vec<IdentifiedChannelEnd>, Error> {
+        self.inner.query_connection_channels(request)
+    }
+
+    fn query_channels(
+        &self,
+        request: QueryChannelsRequest,
+    ) -> Result<Vec<IdentifiedChannelEnd>, Error> {
+        self.inner.query_channels(request)
+    }
+
+    fn query_channel(&self, request: QueryChannelRequest) -> Result<ChannelEnd, Error> {
+        if cacheable_height(request.height).is_none() {
+            return self.inner.query_channel(request);
+        }
+
+        let key = (request.port_id.clone(), request.channel_id.clone());
+
+        if let Some(cached) = self.channel_cache.lock().unwrap().get(&key) {
+            return Ok(cached.clone());
+        }
+
+        let channel = self.inner.query_channel(request)?;
+
+        // Unlike a connection, `Open` isn't terminal for a channel — it can
+        // still close (`ChanCloseInit`/`ChanCloseConfirm`, or implicitly on
+        // an ordered channel's timeout). Only `Closed` is safe to cache
+        // forever; anything else has to be re-queried every time.
+        if *channel.state() == ChannelState::Closed {
+            self.channel_cache.lock().unwrap().put(key, channel.clone());
+        }
+
+        Ok(channel)
+    }
+
+    fn query_channel_client_state(
+        &self,
+        request: QueryChannelClientStateRequest,
+    ) -> Result<Option<IdentifiedAnyClientState>, Error> {
+        self.inner.query_channel_client_state(request)
+    }
+
+    fn query_packet_commitments(
+        &self,
+        request: QueryPacketCommitmentsRequest,
+    ) -> Result<(Vec<Sequence>, Height), Error> {
+        self.inner.query_packet_commitments(request)
+    }
+
+    fn query_unreceived_packets(
+        &self,
+        request: QueryUnreceivedPacketsRequest,
+    ) -> Result<Vec<Sequence>, Error> {
+        self.inner.query_unreceived_packets(request)
+    }
+
+    fn query_packet_acknowledgements(
+        &self,
+        request: QueryPacketAcknowledgementsRequest,
+    ) -> Result<(Vec<Sequence>, Height), Error> {
+        self.inner.query_packet_acknowledgements(request)
+    }
+
+    fn query_unreceived_acknowledgements(
+        &self,
+        request: QueryUnreceivedAcksRequest,
+    ) -> Result<Vec<Sequence>, Error> {
+        self.inner.query_unreceived_acknowledgements(request)
+    }
+
+    fn query_next_sequence_receive(
+        &self,
+        request: QueryNextSequenceReceiveRequest,
+    ) -> Result<Sequence, Error> {
+        self.inner.query_next_sequence_receive(request)
+    }
+
+    fn query_txs(&self, request: QueryTxRequest) -> Result<Vec<IbcEvent>, Error> {
+        self.inner.query_txs(request)
+    }
+
+    fn query_blocks(
+        &self,
+        request: QueryBlockRequest,
+    ) -> Result<(Vec<IbcEvent>, Vec<IbcEvent>), Error> {
+        self.inner.query_blocks(request)
+    }
+
+    fn query_host_consensus_state(
+        &self,
+        request: QueryHostConsensusStateRequest,
+    ) -> Result<Self::ConsensusState, Error> {
+        self.inner.query_host_consensus_state(request)
+    }
+
+    fn proven_client_state(
+        &self,
+        client_id: &ClientId,
+        height: Height,
+    ) -> Result<(AnyClientState, MerkleProof), Error> {
+        if !is_cacheable(height) {
+            return self.inner.proven_client_state(client_id, height);
+        }
+
+        let key = (client_id.clone(), height);
+
+        if let Some(cached) = self.proven_client_state_cache.lock().unwrap().get(&key) {
+            return Ok(cached.clone());
+        }
+
+        let proven = self.inner.proven_client_state(client_id, height)?;
+        self.proven_client_state_cache
+            .lock()
+            .unwrap()
+            .put(key, proven.clone());
+
+        Ok(proven)
+    }
+
+    fn proven_connection(
+        &self,
+        connection_id: &ConnectionId,
+        height: Height,
+    ) -> Result<(ConnectionEnd, MerkleProof), Error> {
+        if !is_cacheable(height) {
+            return self.inner.proven_connection(connection_id, height);
+        }
+
+        let key = (connection_id.clone(), height);
+
+        if let Some(cached) = self.proven_connection_cache.lock().unwrap().get(&key) {
+            return Ok(cached.clone());
+        }
+
+        let proven = self.inner.proven_connection(connection_id, height)?;
+        self.proven_connection_cache
+            .lock()
+            .unwrap()
+            .put(key, proven.clone());
+
+        Ok(proven)
+    }
+
+    fn proven_client_consensus(
+        &self,
+        client_id: &ClientId,
+        consensus_height: Height,
+        height: Height,
+    ) -> Result<(AnyConsensusState, MerkleProof), Error> {
+        if !is_cacheable(height) {
+            return self
+                .inner
+                .proven_client_consensus(client_id, consensus_height, height);
+        }
+
+        let key = (client_id.clone(), consensus_height, height);
+
+        if let Some(cached) = self.proven_consensus_cache.lock().unwrap().get(&key) {
+            return Ok(cached.clone());
+        }
+
+        let proven = self
+            .inner
+            .proven_client_consensus(client_id, consensus_height, height)?;
+        self.proven_consensus_cache
+            .lock()
+            .unwrap()
+            .put(key, proven.clone());
+
+        Ok(proven)
+    }
+
+    fn proven_channel(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        height: Height,
+    ) -> Result<(ChannelEnd, MerkleProof), Error> {
+        if !is_cacheable(height) {
+            return self.inner.proven_channel(port_id, channel_id, height);
+        }
+
+        let key = (port_id.clone(), channel_id.clone(), height);
+
+        if let Some(cached) = self.proven_channel_cache.lock().unwrap().get(&key) {
+            return Ok(cached.clone());
+        }
+
+        let proven = self.inner.proven_channel(port_id, channel_id, height)?;
+        self.proven_channel_cache
+            .lock()
+            .unwrap()
+            .put(key, proven.clone());
+
+        Ok(proven)
+    }
+
+    fn proven_packet(
+        &self,
+        packet_type: PacketMsgType,
+        port_id: PortId,
+        channel_id: ChannelId,
+        sequence: Sequence,
+        height: Height,
+    ) -> Result<(Vec<u8>, MerkleProof), Error> {
+        if !is_cacheable(height) {
+            return self
+                .inner
+                .proven_packet(packet_type, port_id, channel_id, sequence, height);
+        }
+
+        let key = (packet_type, port_id.clone(), channel_id.clone(), sequence, height);
+
+        if let Some(cached) = self.proven_packet_cache.lock().unwrap().get(&key) {
+            return Ok(cached.clone());
+        }
+
+        let proven = self
+            .inner
+            .proven_packet(packet_type, port_id, channel_id, sequence, height)?;
+        self.proven_packet_cache
+            .lock()
+            .unwrap()
+            .put(key, proven.clone());
+
+        Ok(proven)
+    }
+
+    fn build_client_state(
+        &self,
+        height: Height,
+        settings: ClientSettings,
+    ) -> Result<Self::ClientState, Error> {
+        self.inner.build_client_state(height, settings)
+    }
+
+    fn build_consensus_state(
+        &self,
+        light_block: Self::LightBlock,
+    ) -> Result<Self::ConsensusState, Error> {
+        self.inner.build_consensus_state(light_block)
+    }
+
+    fn build_header(
+        &self,
+        trusted_height: Height,
+        target_height: Height,
+        client_state: &AnyClientState,
+        light_client: &mut Self::LightClient,
+    ) -> Result<(Self::Header, Vec<Self::Header>), Error> {
+        self.inner.build_header(
+            trusted_height,
+            target_height,
+            client_state,
+            &mut light_client.0,
+        )
+    }
+}
+
+pub struct CachingLightClient<C: ChainEndpoint>(C::LightClient);
+
+impl<C: ChainEndpoint> LightClient<CachingChain<C>> for CachingLightClient<C> {
+    fn header_and_minimal_set(
+        &mut self,
+        trusted: Height,
+        target: Height,
+        client_state: &AnyClientState,
+    ) -> Result<Verified<<CachingChain<C> as ChainEndpoint>::Header>, Error> {
+        self.0.header_and_minimal_set(trusted, target, client_state)
+    }
+
+    fn verify(
+        &mut self,
+        trusted: Height,
+        target: Height,
+        client_state: &AnyClientState,
+    ) -> Result<Verified<<CachingChain<C> as ChainEndpoint>::LightBlock>, Error> {
+        self.0.verify(trusted, target, client_state)
+    }
+
+    fn check_misbehaviour(
+        &mut self,
+        update: UpdateClient,
+        client_state: &AnyClientState,
+    ) -> Result<Option<MisbehaviourEvidence>, Error> {
+        self.0.check_misbehaviour(update, client_state)
+    }
+
+    fn fetch(
+        &mut self,
+        height: Height,
+    ) -> Result<<CachingChain<C> as ChainEndpoint>::LightBlock, Error> {
+        self.0.fetch(height)
+    }
+}
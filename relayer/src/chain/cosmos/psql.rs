@@ -1,10 +1,10 @@
-#![allow(unused_variables, dead_code)]
-
 use std::sync::Arc;
+use std::thread;
 
 use semver::Version;
-use sqlx::postgres::{PgPool, PgPoolOptions};
-use tracing::info;
+use sqlx::postgres::{PgPool, PgPoolOptions, PgRow};
+use sqlx::Row;
+use tracing::{error, info, trace};
 
 use tendermint_rpc::endpoint::broadcast::tx_sync;
 
@@ -25,7 +25,7 @@ use ibc::{
         ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId},
     },
     events::IbcEvent,
-    query::{QueryBlockRequest, QueryTxRequest},
+    query::{QueryBlockRequest, QueryPacketEventDataRequest, QueryTxRequest},
     Height,
 };
 
@@ -39,7 +39,10 @@ use crate::{
     },
     config::ChainConfig,
     error::Error,
-    event::monitor::{EventReceiver, TxMonitorCmd},
+    event::{
+        monitor::{EventBatch, EventReceiver, TxMonitorCmd},
+        IbcEventWithHeight,
+    },
     keyring::{KeyEntry, KeyRing},
     light_client::{tendermint::LightClient as TmLightClient, LightClient, Verified},
 };
@@ -54,6 +57,403 @@ flex_error::define_error! {
     }
 }
 
+/// Name of the table backing the Postgres-indexed query path.
+///
+/// Rows are keyed by `(chain_id, block_height, tx_hash, event_index)` and hold
+/// one serialized [`IbcEvent`] each, plus the packet routing columns
+/// (`event_type`, `port_id`, `channel_id`, `sequence`) that the batched
+/// queries below filter on so they don't have to deserialize every row.
+const IBC_JSON_TABLE: &str = "ibc_json";
+
+async fn init_schema(pool: &PgPool) -> Result<(), Error> {
+    sqlx::query(&format!(
+        "CREATE TABLE IF NOT EXISTS {IBC_JSON_TABLE} (
+            chain_id TEXT NOT NULL,
+            block_height BIGINT NOT NULL,
+            tx_hash TEXT NOT NULL,
+            event_index BIGINT NOT NULL,
+            event_type TEXT NOT NULL,
+            port_id TEXT,
+            channel_id TEXT,
+            sequence BIGINT,
+            event JSONB NOT NULL,
+            PRIMARY KEY (chain_id, block_height, tx_hash, event_index)
+        )"
+    ))
+    .execute(pool)
+    .await
+    .map_err(Error::sqlx)?;
+
+    // The packet queries below filter on these columns (plus `sequence`);
+    // the primary key only narrows them down to `chain_id`, so without this
+    // index each one scans every row indexed for the chain.
+    sqlx::query(&format!(
+        "CREATE INDEX IF NOT EXISTS ibc_json_packet_route_idx
+         ON {IBC_JSON_TABLE} (chain_id, port_id, channel_id, event_type, sequence)"
+    ))
+    .execute(pool)
+    .await
+    .map_err(Error::sqlx)?;
+
+    Ok(())
+}
+
+/// The packet routing columns we index alongside the raw event, when the
+/// event carries them. Events that don't (eg. `CreateClient`) are still
+/// stored, with these columns left `NULL`.
+struct PacketRoute {
+    event_type: &'static str,
+    port_id: Option<String>,
+    channel_id: Option<String>,
+    sequence: Option<i64>,
+}
+
+fn packet_route(event: &IbcEvent) -> PacketRoute {
+    fn route(
+        event_type: &'static str,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        sequence: Sequence,
+    ) -> PacketRoute {
+        PacketRoute {
+            event_type,
+            port_id: Some(port_id.to_string()),
+            channel_id: Some(channel_id.to_string()),
+            sequence: Some(u64::from(sequence) as i64),
+        }
+    }
+
+    match event {
+        IbcEvent::SendPacket(e) => route(
+            "send_packet",
+            &e.packet.source_port,
+            &e.packet.source_channel,
+            e.packet.sequence,
+        ),
+        IbcEvent::ReceivePacket(e) => route(
+            "recv_packet",
+            &e.packet.destination_port,
+            &e.packet.destination_channel,
+            e.packet.sequence,
+        ),
+        IbcEvent::WriteAcknowledgement(e) => route(
+            "write_acknowledgement",
+            &e.packet.destination_port,
+            &e.packet.destination_channel,
+            e.packet.sequence,
+        ),
+        IbcEvent::AcknowledgePacket(e) => route(
+            "acknowledge_packet",
+            &e.packet.source_port,
+            &e.packet.source_channel,
+            e.packet.sequence,
+        ),
+        IbcEvent::TimeoutPacket(e) => route(
+            "timeout_packet",
+            &e.packet.source_port,
+            &e.packet.source_channel,
+            e.packet.sequence,
+        ),
+        _ => PacketRoute {
+            event_type: event.event_type().as_str(),
+            port_id: None,
+            channel_id: None,
+            sequence: None,
+        },
+    }
+}
+
+async fn upsert_ibc_event(
+    pool: &PgPool,
+    chain_id: &ChainId,
+    block_height: Height,
+    tx_hash: &str,
+    event_index: i64,
+    event: &IbcEvent,
+) -> Result<(), Error> {
+    let route = packet_route(event);
+    let payload = serde_json::to_value(event).map_err(Error::serde_json)?;
+
+    sqlx::query(&format!(
+        "INSERT INTO {IBC_JSON_TABLE}
+            (chain_id, block_height, tx_hash, event_index, event_type, port_id, channel_id, sequence, event)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+         ON CONFLICT (chain_id, block_height, tx_hash, event_index) DO UPDATE SET event = EXCLUDED.event"
+    ))
+    .bind(chain_id.as_str())
+    .bind(block_height.revision_height() as i64)
+    .bind(tx_hash)
+    .bind(event_index)
+    .bind(route.event_type)
+    .bind(route.port_id)
+    .bind(route.channel_id)
+    .bind(route.sequence)
+    .bind(payload)
+    .execute(pool)
+    .await
+    .map_err(Error::sqlx)?;
+
+    Ok(())
+}
+
+fn row_to_event(row: &PgRow) -> Result<IbcEvent, Error> {
+    let payload: serde_json::Value = row.try_get("event").map_err(Error::sqlx)?;
+    serde_json::from_value(payload).map_err(Error::serde_json)
+}
+
+/// Tees every event coming out of the inner chain's event monitor into
+/// `ibc_json`, on a dedicated thread, then forwards the original batch
+/// downstream unchanged so relaying itself still observes events in
+/// real time and isn't gated on the write succeeding.
+///
+/// A batch that fails to index (eg. a transient Postgres outage) is only
+/// logged, not retried: the index silently falls behind the chain for
+/// that batch, which the accelerator queries below have no way to detect.
+// TODO(romac): retry `index_batch` with backoff, and/or backfill missed
+// batches from RPC by height range, instead of dropping them.
+fn tee_events_into_postgres(
+    chain_id: ChainId,
+    inner: EventReceiver,
+    pool: PgPool,
+    rt: Arc<tokio::runtime::Runtime>,
+) -> EventReceiver {
+    let (tx, rx) = crossbeam_channel::unbounded();
+
+    thread::spawn(move || {
+        while let Ok(batch) = inner.recv() {
+            if let Err(e) = rt.block_on(index_batch(&pool, &chain_id, &batch)) {
+                error!("failed to index event batch into postgres: {}", e);
+            }
+
+            if tx.send(batch).is_err() {
+                break;
+            }
+        }
+    });
+
+    rx
+}
+
+async fn index_batch(pool: &PgPool, chain_id: &ChainId, batch: &EventBatch) -> Result<(), Error> {
+    // The monitor delivers events batched per block rather than per tx, so
+    // there's no tx hash to key on here; `query_txs`' `Transaction` variant
+    // still falls through to RPC, where the real hash is available.
+    let tx_hash = "";
+
+    for (index, IbcEventWithHeight { event, height }) in batch.events.iter().enumerate() {
+        trace!("indexing {} at {}", event, height);
+        upsert_ibc_event(pool, chain_id, *height, tx_hash, index as i64, event).await?;
+    }
+
+    Ok(())
+}
+
+async fn query_packet_commitment_sequences(
+    pool: &PgPool,
+    chain_id: &ChainId,
+    port_id: &PortId,
+    channel_id: &ChannelId,
+) -> Result<Vec<Sequence>, Error> {
+    // A commitment is still outstanding as long as we haven't also recorded
+    // the ack or the timeout that clears it.
+    let rows = sqlx::query(&format!(
+        "SELECT sequence FROM {IBC_JSON_TABLE}
+         WHERE chain_id = $1 AND port_id = $2 AND channel_id = $3 AND event_type = 'send_packet'
+           AND NOT EXISTS (
+             SELECT 1 FROM {IBC_JSON_TABLE} r
+             WHERE r.chain_id = $1 AND r.port_id = $2 AND r.channel_id = $3
+               AND r.event_type IN ('acknowledge_packet', 'timeout_packet')
+               AND r.sequence = {IBC_JSON_TABLE}.sequence
+           )
+         ORDER BY sequence"
+    ))
+    .bind(chain_id.as_str())
+    .bind(port_id.as_str())
+    .bind(channel_id.as_str())
+    .fetch_all(pool)
+    .await
+    .map_err(Error::sqlx)?;
+
+    sequences_from_rows(rows)
+}
+
+async fn query_unreceived_packets(
+    pool: &PgPool,
+    chain_id: &ChainId,
+    port_id: &PortId,
+    channel_id: &ChannelId,
+    commitment_sequences: &[Sequence],
+) -> Result<Vec<Sequence>, Error> {
+    // `commitment_sequences` are the commitments still outstanding on the
+    // *counterparty* chain; `send_packet` rows for them live under the
+    // counterparty's own `chain_id`, never ours, so we can't look those up
+    // here. What we can tell locally is which of those sequences we've
+    // already recorded a `recv_packet` for — the rest are unreceived.
+    let received =
+        query_received_sequences(pool, chain_id, port_id, channel_id, commitment_sequences)
+            .await?;
+
+    Ok(unreceived_sequences(commitment_sequences, &received))
+}
+
+async fn query_received_sequences(
+    pool: &PgPool,
+    chain_id: &ChainId,
+    port_id: &PortId,
+    channel_id: &ChannelId,
+    commitment_sequences: &[Sequence],
+) -> Result<Vec<Sequence>, Error> {
+    let sequences = to_i64s(commitment_sequences);
+
+    let rows = sqlx::query(&format!(
+        "SELECT sequence FROM {IBC_JSON_TABLE}
+         WHERE chain_id = $1 AND port_id = $2 AND channel_id = $3
+           AND event_type = 'recv_packet' AND sequence = ANY($4)
+         ORDER BY sequence"
+    ))
+    .bind(chain_id.as_str())
+    .bind(port_id.as_str())
+    .bind(channel_id.as_str())
+    .bind(sequences)
+    .fetch_all(pool)
+    .await
+    .map_err(Error::sqlx)?;
+
+    sequences_from_rows(rows)
+}
+
+/// Of the sequences the counterparty still has commitments for, returns
+/// those we have no local `recv_packet` row for yet.
+fn unreceived_sequences(commitment_sequences: &[Sequence], received: &[Sequence]) -> Vec<Sequence> {
+    commitment_sequences
+        .iter()
+        .copied()
+        .filter(|sequence| !received.contains(sequence))
+        .collect()
+}
+
+async fn query_packet_ack_sequences(
+    pool: &PgPool,
+    chain_id: &ChainId,
+    port_id: &PortId,
+    channel_id: &ChannelId,
+    commitment_sequences: &[Sequence],
+) -> Result<Vec<Sequence>, Error> {
+    let sequences = to_i64s(commitment_sequences);
+
+    let rows = sqlx::query(&format!(
+        "SELECT sequence FROM {IBC_JSON_TABLE}
+         WHERE chain_id = $1 AND port_id = $2 AND channel_id = $3
+           AND event_type = 'write_acknowledgement' AND sequence = ANY($4)
+         ORDER BY sequence"
+    ))
+    .bind(chain_id.as_str())
+    .bind(port_id.as_str())
+    .bind(channel_id.as_str())
+    .bind(sequences)
+    .fetch_all(pool)
+    .await
+    .map_err(Error::sqlx)?;
+
+    sequences_from_rows(rows)
+}
+
+async fn query_unreceived_acknowledgements(
+    pool: &PgPool,
+    chain_id: &ChainId,
+    port_id: &PortId,
+    channel_id: &ChannelId,
+    ack_sequences: &[Sequence],
+) -> Result<Vec<Sequence>, Error> {
+    let sequences = to_i64s(ack_sequences);
+
+    let rows = sqlx::query(&format!(
+        "SELECT sequence FROM {IBC_JSON_TABLE}
+         WHERE chain_id = $1 AND port_id = $2 AND channel_id = $3
+           AND event_type = 'send_packet' AND sequence = ANY($4)
+           AND NOT EXISTS (
+             SELECT 1 FROM {IBC_JSON_TABLE} r
+             WHERE r.chain_id = $1 AND r.port_id = $2 AND r.channel_id = $3
+               AND r.event_type = 'acknowledge_packet'
+               AND r.sequence = {IBC_JSON_TABLE}.sequence
+           )
+         ORDER BY sequence"
+    ))
+    .bind(chain_id.as_str())
+    .bind(port_id.as_str())
+    .bind(channel_id.as_str())
+    .bind(sequences)
+    .fetch_all(pool)
+    .await
+    .map_err(Error::sqlx)?;
+
+    sequences_from_rows(rows)
+}
+
+async fn query_packet_events(
+    pool: &PgPool,
+    chain_id: &ChainId,
+    request: &QueryPacketEventDataRequest,
+) -> Result<Vec<IbcEvent>, Error> {
+    let sequences = to_i64s(&request.sequences);
+
+    // `packet_route` indexes `send_packet`/`acknowledge_packet`/`timeout_packet`
+    // under the packet's source channel, and `recv_packet`/`write_acknowledgement`
+    // under its destination channel — look each event type up on the same side
+    // it was indexed on.
+    let channel_id = match request.event_id.as_str() {
+        "send_packet" | "acknowledge_packet" | "timeout_packet" => &request.source_channel_id,
+        _ => &request.destination_channel_id,
+    };
+
+    let rows = sqlx::query(&format!(
+        "SELECT event FROM {IBC_JSON_TABLE}
+         WHERE chain_id = $1 AND event_type = $2 AND channel_id = $3 AND sequence = ANY($4)
+         ORDER BY block_height, event_index"
+    ))
+    .bind(chain_id.as_str())
+    .bind(request.event_id.as_str())
+    .bind(channel_id.as_str())
+    .bind(sequences)
+    .fetch_all(pool)
+    .await
+    .map_err(Error::sqlx)?;
+
+    rows.iter().map(row_to_event).collect()
+}
+
+async fn query_events_at_height(
+    pool: &PgPool,
+    chain_id: &ChainId,
+    height: Height,
+) -> Result<Vec<IbcEvent>, Error> {
+    let rows = sqlx::query(&format!(
+        "SELECT event FROM {IBC_JSON_TABLE}
+         WHERE chain_id = $1 AND block_height = $2
+         ORDER BY event_index"
+    ))
+    .bind(chain_id.as_str())
+    .bind(height.revision_height() as i64)
+    .fetch_all(pool)
+    .await
+    .map_err(Error::sqlx)?;
+
+    rows.iter().map(row_to_event).collect()
+}
+
+fn to_i64s(sequences: &[Sequence]) -> Vec<i64> {
+    sequences.iter().map(|s| u64::from(*s) as i64).collect()
+}
+
+fn sequences_from_rows(rows: Vec<PgRow>) -> Result<Vec<Sequence>, Error> {
+    rows.iter()
+        .map(|row| {
+            let sequence: i64 = row.try_get("sequence").map_err(Error::sqlx)?;
+            Ok(Sequence::from(sequence as u64))
+        })
+        .collect()
+}
+
 pub struct PsqlChain {
     chain: CosmosSdkChain,
     pool: PgPool,
@@ -83,6 +483,8 @@ impl ChainEndpoint for PsqlChain {
             .block_on(PgPoolOptions::new().max_connections(5).connect(psql_conn))
             .map_err(Error::sqlx)?;
 
+        rt.block_on(init_schema(&pool))?;
+
         info!("instantiating chain");
 
         let chain = CosmosSdkChain::bootstrap(config, rt.clone())?;
@@ -98,12 +500,19 @@ impl ChainEndpoint for PsqlChain {
         &self,
         rt: Arc<tokio::runtime::Runtime>,
     ) -> Result<(EventReceiver, TxMonitorCmd), Error> {
-        self.chain.init_event_monitor(rt)
+        let (events, monitor_cmd) = self.chain.init_event_monitor(rt)?;
+
+        let events = tee_events_into_postgres(
+            self.chain.id().clone(),
+            events,
+            self.pool.clone(),
+            self.rt.clone(),
+        );
+
+        Ok((events, monitor_cmd))
     }
 
     fn id(&self) -> &ChainId {
-        // let _ = &self.pool;
-        // let _ = &self.rt;
         self.chain.id()
     }
 
@@ -260,28 +669,61 @@ impl ChainEndpoint for PsqlChain {
         &self,
         request: QueryPacketCommitmentsRequest,
     ) -> Result<(Vec<Sequence>, Height), Error> {
-        self.chain.query_packet_commitments(request)
+        // The current chain height still has to come from the node: the
+        // index only knows about the events it has seen, not the head.
+        let height = self.chain.query_application_status()?.height;
+
+        let sequences = self.rt.block_on(query_packet_commitment_sequences(
+            &self.pool,
+            self.chain.id(),
+            &request.port_id,
+            &request.channel_id,
+        ))?;
+
+        Ok((sequences, height))
     }
 
     fn query_unreceived_packets(
         &self,
         request: QueryUnreceivedPacketsRequest,
     ) -> Result<Vec<Sequence>, Error> {
-        self.chain.query_unreceived_packets(request)
+        self.rt.block_on(query_unreceived_packets(
+            &self.pool,
+            self.chain.id(),
+            &request.port_id,
+            &request.channel_id,
+            &request.packet_commitment_sequences,
+        ))
     }
 
     fn query_packet_acknowledgements(
         &self,
         request: QueryPacketAcknowledgementsRequest,
     ) -> Result<(Vec<Sequence>, Height), Error> {
-        self.chain.query_packet_acknowledgements(request)
+        let height = self.chain.query_application_status()?.height;
+
+        let sequences = self.rt.block_on(query_packet_ack_sequences(
+            &self.pool,
+            self.chain.id(),
+            &request.port_id,
+            &request.channel_id,
+            &request.packet_commitment_sequences,
+        ))?;
+
+        Ok((sequences, height))
     }
 
     fn query_unreceived_acknowledgements(
         &self,
         request: QueryUnreceivedAcksRequest,
     ) -> Result<Vec<Sequence>, Error> {
-        self.chain.query_unreceived_acknowledgements(request)
+        self.rt.block_on(query_unreceived_acknowledgements(
+            &self.pool,
+            self.chain.id(),
+            &request.port_id,
+            &request.channel_id,
+            &request.packet_ack_sequences,
+        ))
     }
 
     fn query_next_sequence_receive(
@@ -292,14 +734,30 @@ impl ChainEndpoint for PsqlChain {
     }
 
     fn query_txs(&self, request: QueryTxRequest) -> Result<Vec<IbcEvent>, Error> {
-        self.chain.query_txs(request)
+        match &request {
+            QueryTxRequest::Packet(packet_request) => self.rt.block_on(query_packet_events(
+                &self.pool,
+                self.chain.id(),
+                packet_request,
+            )),
+            // We don't index by tx hash, so hash lookups still go to the node.
+            QueryTxRequest::Transaction(_) => self.chain.query_txs(request),
+        }
     }
 
     fn query_blocks(
         &self,
         request: QueryBlockRequest,
     ) -> Result<(Vec<IbcEvent>, Vec<IbcEvent>), Error> {
-        self.chain.query_blocks(request)
+        // All IBC events are emitted in `EndBlock`, so every indexed row at
+        // this height belongs in `end_block_events`.
+        let end_block_events = self.rt.block_on(query_events_at_height(
+            &self.pool,
+            self.chain.id(),
+            request.height,
+        ))?;
+
+        Ok((Vec::new(), end_block_events))
     }
 
     fn query_host_consensus_state(
@@ -420,3 +878,67 @@ impl LightClient<PsqlChain> for PsqlLightClient {
         self.0.fetch(height)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seqs(raw: &[u64]) -> Vec<Sequence> {
+        raw.iter().copied().map(Sequence::from).collect()
+    }
+
+    #[test]
+    fn unreceived_sequences_excludes_locally_received() {
+        let commitments = seqs(&[1, 2, 3, 4]);
+        let received = seqs(&[2, 4]);
+
+        assert_eq!(unreceived_sequences(&commitments, &received), seqs(&[1, 3]));
+    }
+
+    #[test]
+    fn unreceived_sequences_is_all_commitments_when_nothing_received() {
+        let commitments = seqs(&[1, 2, 3]);
+
+        assert_eq!(unreceived_sequences(&commitments, &[]), commitments);
+    }
+
+    #[test]
+    fn unreceived_sequences_is_empty_when_everything_received() {
+        let commitments = seqs(&[1, 2, 3]);
+
+        assert!(unreceived_sequences(&commitments, &commitments).is_empty());
+    }
+
+    fn test_packet() -> ibc::core::ics04_channel::packet::Packet {
+        ibc::core::ics04_channel::packet::Packet {
+            sequence: Sequence::from(1),
+            source_port: "transfer".parse().unwrap(),
+            source_channel: "channel-0".parse().unwrap(),
+            destination_port: "transfer".parse().unwrap(),
+            destination_channel: "channel-1".parse().unwrap(),
+            data: vec![],
+            timeout_height: Default::default(),
+            timeout_timestamp: Default::default(),
+        }
+    }
+
+    fn write_ack_event() -> IbcEvent {
+        IbcEvent::WriteAcknowledgement(ibc::core::ics04_channel::events::WriteAcknowledgement {
+            packet: test_packet(),
+            ack: vec![],
+        })
+    }
+
+    /// `packet_route` indexes `write_acknowledgement` rows, and
+    /// `query_packet_events` looks them back up, by channel — this guards
+    /// against the two drifting apart the way they did for chunk0-1.
+    #[test]
+    fn write_ack_is_indexed_on_the_side_query_packet_events_looks_it_up_on() {
+        let route = packet_route(&write_ack_event());
+
+        let indexed_channel = route.channel_id.expect("write_ack rows carry a channel_id");
+        let looked_up_channel = test_packet().destination_channel.to_string();
+
+        assert_eq!(indexed_channel, looked_up_channel);
+    }
+}
@@ -0,0 +1,466 @@
+//! A `tonic`-based gRPC server that answers the standard IBC query services
+//! (client/connection/channel/packet state) directly from a [`ChainEndpoint`],
+//! mirroring the blanket gRPC service support in ibc-rs. This lets external
+//! tooling and light-client provers query the relayer itself instead of the
+//! full node. Behind a [`super::cosmos::psql::PsqlChain`], `PacketCommitments`
+//! is answered from its Postgres index (only the height comes from the node);
+//! the other implemented RPCs go through `PsqlChain` to the node.
+//!
+//! Gated behind the `grpc` feature, which pulls in the optional `tonic`
+//! dependency (see this crate's `Cargo.toml`).
+//!
+//! Only `ClientState`, `Connection`, `Channel` and `PacketCommitments` are
+//! implemented. Every other RPC of each service answers
+//! `Status::unimplemented`, including those for which a [`ChainEndpoint`]
+//! query exists (eg. `UnreceivedPackets`, `PacketAcknowledgements`).
+#![cfg(feature = "grpc")]
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use tonic::{Request, Response, Status};
+
+use ibc_proto::ibc::core::channel::v1::{
+    query_server::{Query as ChannelQuery, QueryServer as ChannelQueryServer},
+    PacketState,
+    QueryChannelClientStateRequest,
+    QueryChannelClientStateResponse,
+    QueryChannelConsensusStateRequest,
+    QueryChannelConsensusStateResponse,
+    QueryChannelRequest as RawQueryChannelRequest,
+    QueryChannelResponse,
+    QueryChannelsRequest,
+    QueryChannelsResponse,
+    QueryConnectionChannelsRequest,
+    QueryConnectionChannelsResponse,
+    QueryNextSequenceReceiveRequest as RawQueryNextSequenceReceiveRequest,
+    QueryNextSequenceReceiveResponse,
+    QueryPacketAcknowledgementRequest,
+    QueryPacketAcknowledgementResponse,
+    QueryPacketAcknowledgementsRequest as RawQueryPacketAcknowledgementsRequest,
+    QueryPacketAcknowledgementsResponse,
+    QueryPacketCommitmentRequest,
+    QueryPacketCommitmentResponse,
+    QueryPacketCommitmentsRequest as RawQueryPacketCommitmentsRequest,
+    QueryPacketCommitmentsResponse,
+    QueryPacketReceiptRequest,
+    QueryPacketReceiptResponse,
+    QueryUnreceivedAcksRequest as RawQueryUnreceivedAcksRequest,
+    QueryUnreceivedAcksResponse,
+    QueryUnreceivedPacketsRequest as RawQueryUnreceivedPacketsRequest,
+    QueryUnreceivedPacketsResponse,
+};
+use ibc_proto::ibc::core::client::v1::{
+    query_server::{Query as ClientQuery, QueryServer as ClientQueryServer},
+    QueryClientStateRequest as RawQueryClientStateRequest,
+    QueryClientStateResponse,
+    QueryClientStatesRequest,
+    QueryClientStatesResponse,
+    QueryClientStatusRequest,
+    QueryClientStatusResponse,
+    QueryConsensusStateRequest,
+    QueryConsensusStateResponse,
+    QueryConsensusStatesRequest,
+    QueryConsensusStatesResponse,
+    QueryUpgradedClientStateRequest,
+    QueryUpgradedClientStateResponse,
+    QueryUpgradedConsensusStateRequest,
+    QueryUpgradedConsensusStateResponse,
+};
+use ibc_proto::ibc::core::connection::v1::{
+    query_server::{Query as ConnectionQuery, QueryServer as ConnectionQueryServer},
+    QueryClientConnectionsRequest,
+    QueryClientConnectionsResponse,
+    QueryConnectionClientStateRequest,
+    QueryConnectionClientStateResponse,
+    QueryConnectionConsensusStateRequest,
+    QueryConnectionConsensusStateResponse,
+    QueryConnectionRequest as RawQueryConnectionRequest,
+    QueryConnectionResponse,
+    QueryConnectionsRequest,
+    QueryConnectionsResponse,
+};
+use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
+
+use crate::chain::endpoint::ChainEndpoint;
+use crate::chain::requests::{
+    QueryChannelRequest, QueryClientStateRequest, QueryConnectionRequest, QueryHeight,
+    QueryPacketCommitmentsRequest,
+};
+use crate::error::Error;
+
+fn to_status(err: Error) -> Status {
+    Status::internal(err.to_string())
+}
+
+fn unimplemented<T>(rpc: &str) -> Result<Response<T>, Status> {
+    Err(Status::unimplemented(format!(
+        "{rpc} is not served by the relayer's gRPC server"
+    )))
+}
+
+/// Shared handle to the chain backing the gRPC services below. `ChainEndpoint`
+/// queries only need `&self`, so an `Arc` is enough to hand the same endpoint
+/// to every service without a lock.
+struct Shared<C>(Arc<C>);
+
+impl<C> Clone for Shared<C> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+pub struct ClientQueryService<C: ChainEndpoint>(Shared<C>);
+
+#[tonic::async_trait]
+impl<C: ChainEndpoint + 'static> ClientQuery for ClientQueryService<C> {
+    async fn client_state(
+        &self,
+        request: Request<RawQueryClientStateRequest>,
+    ) -> Result<Response<QueryClientStateResponse>, Status> {
+        let request = request.into_inner();
+
+        let client_id: ClientId = request
+            .client_id
+            .parse()
+            .map_err(|_| Status::invalid_argument("invalid client id"))?;
+
+        let client_state = self
+            .0
+             .0
+            .query_client_state(QueryClientStateRequest {
+                client_id,
+                height: QueryHeight::Latest,
+            })
+            .map_err(to_status)?;
+
+        Ok(Response::new(QueryClientStateResponse {
+            client_state: Some(client_state.into()),
+            proof: vec![],
+            proof_height: None,
+        }))
+    }
+
+    async fn client_states(
+        &self,
+        _request: Request<QueryClientStatesRequest>,
+    ) -> Result<Response<QueryClientStatesResponse>, Status> {
+        unimplemented("client_states")
+    }
+
+    async fn consensus_state(
+        &self,
+        _request: Request<QueryConsensusStateRequest>,
+    ) -> Result<Response<QueryConsensusStateResponse>, Status> {
+        unimplemented("consensus_state")
+    }
+
+    async fn consensus_states(
+        &self,
+        _request: Request<QueryConsensusStatesRequest>,
+    ) -> Result<Response<QueryConsensusStatesResponse>, Status> {
+        unimplemented("consensus_states")
+    }
+
+    async fn client_status(
+        &self,
+        _request: Request<QueryClientStatusRequest>,
+    ) -> Result<Response<QueryClientStatusResponse>, Status> {
+        unimplemented("client_status")
+    }
+
+    async fn upgraded_client_state(
+        &self,
+        _request: Request<QueryUpgradedClientStateRequest>,
+    ) -> Result<Response<QueryUpgradedClientStateResponse>, Status> {
+        unimplemented("upgraded_client_state")
+    }
+
+    async fn upgraded_consensus_state(
+        &self,
+        _request: Request<QueryUpgradedConsensusStateRequest>,
+    ) -> Result<Response<QueryUpgradedConsensusStateResponse>, Status> {
+        unimplemented("upgraded_consensus_state")
+    }
+}
+
+pub struct ConnectionQueryService<C: ChainEndpoint>(Shared<C>);
+
+#[tonic::async_trait]
+impl<C: ChainEndpoint + 'static> ConnectionQuery for ConnectionQueryService<C> {
+    async fn connection(
+        &self,
+        request: Request<RawQueryConnectionRequest>,
+    ) -> Result<Response<QueryConnectionResponse>, Status> {
+        let request = request.into_inner();
+
+        let connection_id: ConnectionId = request
+            .connection_id
+            .parse()
+            .map_err(|_| Status::invalid_argument("invalid connection id"))?;
+
+        let connection_end = self
+            .0
+             .0
+            .query_connection(QueryConnectionRequest {
+                connection_id,
+                height: QueryHeight::Latest,
+            })
+            .map_err(to_status)?;
+
+        Ok(Response::new(QueryConnectionResponse {
+            connection: Some(connection_end.into()),
+            proof: vec![],
+            proof_height: None,
+        }))
+    }
+
+    async fn connections(
+        &self,
+        _request: Request<QueryConnectionsRequest>,
+    ) -> Result<Response<QueryConnectionsResponse>, Status> {
+        unimplemented("connections")
+    }
+
+    async fn client_connections(
+        &self,
+        _request: Request<QueryClientConnectionsRequest>,
+    ) -> Result<Response<QueryClientConnectionsResponse>, Status> {
+        unimplemented("client_connections")
+    }
+
+    async fn connection_client_state(
+        &self,
+        _request: Request<QueryConnectionClientStateRequest>,
+    ) -> Result<Response<QueryConnectionClientStateResponse>, Status> {
+        unimplemented("connection_client_state")
+    }
+
+    async fn connection_consensus_state(
+        &self,
+        _request: Request<QueryConnectionConsensusStateRequest>,
+    ) -> Result<Response<QueryConnectionConsensusStateResponse>, Status> {
+        unimplemented("connection_consensus_state")
+    }
+}
+
+pub struct ChannelQueryService<C: ChainEndpoint>(Shared<C>);
+
+#[tonic::async_trait]
+impl<C: ChainEndpoint + 'static> ChannelQuery for ChannelQueryService<C> {
+    async fn channel(
+        &self,
+        request: Request<RawQueryChannelRequest>,
+    ) -> Result<Response<QueryChannelResponse>, Status> {
+        let request = request.into_inner();
+
+        let port_id: PortId = request
+            .port_id
+            .parse()
+            .map_err(|_| Status::invalid_argument("invalid port id"))?;
+        let channel_id: ChannelId = request
+            .channel_id
+            .parse()
+            .map_err(|_| Status::invalid_argument("invalid channel id"))?;
+
+        let channel_end = self
+            .0
+             .0
+            .query_channel(QueryChannelRequest {
+                port_id,
+                channel_id,
+                height: QueryHeight::Latest,
+            })
+            .map_err(to_status)?;
+
+        Ok(Response::new(QueryChannelResponse {
+            channel: Some(channel_end.into()),
+            proof: vec![],
+            proof_height: None,
+        }))
+    }
+
+    async fn channels(
+        &self,
+        _request: Request<QueryChannelsRequest>,
+    ) -> Result<Response<QueryChannelsResponse>, Status> {
+        unimplemented("channels")
+    }
+
+    async fn connection_channels(
+        &self,
+        _request: Request<QueryConnectionChannelsRequest>,
+    ) -> Result<Response<QueryConnectionChannelsResponse>, Status> {
+        unimplemented("connection_channels")
+    }
+
+    async fn channel_client_state(
+        &self,
+        _request: Request<QueryChannelClientStateRequest>,
+    ) -> Result<Response<QueryChannelClientStateResponse>, Status> {
+        unimplemented("channel_client_state")
+    }
+
+    async fn channel_consensus_state(
+        &self,
+        _request: Request<QueryChannelConsensusStateRequest>,
+    ) -> Result<Response<QueryChannelConsensusStateResponse>, Status> {
+        unimplemented("channel_consensus_state")
+    }
+
+    async fn packet_commitment(
+        &self,
+        _request: Request<QueryPacketCommitmentRequest>,
+    ) -> Result<Response<QueryPacketCommitmentResponse>, Status> {
+        unimplemented("packet_commitment")
+    }
+
+    async fn packet_commitments(
+        &self,
+        request: Request<RawQueryPacketCommitmentsRequest>,
+    ) -> Result<Response<QueryPacketCommitmentsResponse>, Status> {
+        let request = request.into_inner();
+
+        let port_id: PortId = request
+            .port_id
+            .parse()
+            .map_err(|_| Status::invalid_argument("invalid port id"))?;
+        let channel_id: ChannelId = request
+            .channel_id
+            .parse()
+            .map_err(|_| Status::invalid_argument("invalid channel id"))?;
+
+        let (sequences, height) = self
+            .0
+             .0
+            .query_packet_commitments(QueryPacketCommitmentsRequest {
+                port_id: port_id.clone(),
+                channel_id: channel_id.clone(),
+                pagination: None,
+            })
+            .map_err(to_status)?;
+
+        Ok(Response::new(QueryPacketCommitmentsResponse {
+            commitments: sequences_to_packet_states(&port_id, &channel_id, sequences),
+            pagination: None,
+            height: Some(height.into()),
+        }))
+    }
+
+    async fn packet_receipt(
+        &self,
+        _request: Request<QueryPacketReceiptRequest>,
+    ) -> Result<Response<QueryPacketReceiptResponse>, Status> {
+        unimplemented("packet_receipt")
+    }
+
+    async fn packet_acknowledgement(
+        &self,
+        _request: Request<QueryPacketAcknowledgementRequest>,
+    ) -> Result<Response<QueryPacketAcknowledgementResponse>, Status> {
+        unimplemented("packet_acknowledgement")
+    }
+
+    async fn packet_acknowledgements(
+        &self,
+        _request: Request<RawQueryPacketAcknowledgementsRequest>,
+    ) -> Result<Response<QueryPacketAcknowledgementsResponse>, Status> {
+        unimplemented("packet_acknowledgements")
+    }
+
+    async fn unreceived_packets(
+        &self,
+        _request: Request<RawQueryUnreceivedPacketsRequest>,
+    ) -> Result<Response<QueryUnreceivedPacketsResponse>, Status> {
+        unimplemented("unreceived_packets")
+    }
+
+    async fn unreceived_acks(
+        &self,
+        _request: Request<RawQueryUnreceivedAcksRequest>,
+    ) -> Result<Response<QueryUnreceivedAcksResponse>, Status> {
+        unimplemented("unreceived_acks")
+    }
+
+    async fn next_sequence_receive(
+        &self,
+        _request: Request<RawQueryNextSequenceReceiveRequest>,
+    ) -> Result<Response<QueryNextSequenceReceiveResponse>, Status> {
+        unimplemented("next_sequence_receive")
+    }
+}
+
+fn sequences_to_packet_states(
+    port_id: &PortId,
+    channel_id: &ChannelId,
+    sequences: Vec<Sequence>,
+) -> Vec<PacketState> {
+    sequences
+        .into_iter()
+        .map(|sequence| PacketState {
+            port_id: port_id.to_string(),
+            channel_id: channel_id.to_string(),
+            sequence: u64::from(sequence),
+            data: vec![],
+        })
+        .collect()
+}
+
+/// Serves the client/connection/channel query services backed by `chain` on
+/// `addr`, until the returned future is dropped or the process exits.
+pub async fn serve<C: ChainEndpoint + 'static>(
+    chain: Arc<C>,
+    addr: SocketAddr,
+) -> Result<(), tonic::transport::Error> {
+    let shared = Shared(chain);
+
+    tonic::transport::Server::builder()
+        .add_service(ClientQueryServer::new(ClientQueryService(shared.clone())))
+        .add_service(ConnectionQueryServer::new(ConnectionQueryService(
+            shared.clone(),
+        )))
+        .add_service(ChannelQueryServer::new(ChannelQueryService(shared)))
+        .serve(addr)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequences_to_packet_states_carries_port_channel_and_sequence() {
+        let port_id: PortId = "transfer".parse().unwrap();
+        let channel_id: ChannelId = "channel-0".parse().unwrap();
+        let sequences = vec![Sequence::from(1), Sequence::from(2)];
+
+        let states = sequences_to_packet_states(&port_id, &channel_id, sequences);
+
+        assert_eq!(
+            states,
+            vec![
+                PacketState {
+                    port_id: "transfer".to_string(),
+                    channel_id: "channel-0".to_string(),
+                    sequence: 1,
+                    data: vec![],
+                },
+                PacketState {
+                    port_id: "transfer".to_string(),
+                    channel_id: "channel-0".to_string(),
+                    sequence: 2,
+                    data: vec![],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn sequences_to_packet_states_is_empty_for_no_commitments() {
+        let port_id: PortId = "transfer".parse().unwrap();
+        let channel_id: ChannelId = "channel-0".parse().unwrap();
+
+        assert!(sequences_to_packet_states(&port_id, &channel_id, vec![]).is_empty());
+    }
+}